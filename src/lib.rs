@@ -1,11 +1,10 @@
 use custom_logger::*;
-use regex::Regex;
 use serde_derive::{Deserialize, Serialize};
 use std::collections::HashMap;
 use std::error::Error;
 use std::fs;
 use std::fs::File;
-use std::io::Read;
+use std::io::{BufReader, Read};
 use std::path::Path;
 use walkdir::WalkDir;
 
@@ -59,12 +58,10 @@ pub struct Property {
     pub value: Value,
 }
 
-#[derive(Serialize, Deserialize, Clone, Debug)]
-#[serde(rename_all = "camelCase")]
-pub struct Value {
-    #[serde(rename = "packageName")]
-    pub package_name: Option<String>,
-}
+// the `value` payload legitimately varies across property types
+// (string, number, null or nested object), so we keep it opaque and
+// let serde_json carry it through verbatim
+pub type Value = serde_json::Value;
 
 impl DeclarativeConfig {
     pub fn get_packages(dir: &String) -> Result<Vec<String>, Box<dyn Error>> {
@@ -109,82 +106,40 @@ impl DeclarativeConfig {
             let file_name = entry.path();
             let file_name_str = file_name.to_string_lossy();
 
-            // Open the path in read-only mode, returns `Result()`
-            let mut f = File::open(file_name)?;
+            // only the aggregated catalogs are broken down into individual
+            // declarative configs, similar to what ibm have done
+            let is_json =
+                file_name_str.contains("catalog.json") || file_name_str.contains("index.json");
+            let is_yaml =
+                file_name_str.contains("catalog.yaml") || file_name_str.contains("index.yaml");
+            if !is_json && !is_yaml {
+                continue;
+            }
 
             let component = file_name_str.split("/configs/").nth(1).unwrap();
             trace!("updating config : {:#?}", component);
 
-            // Read the file contents into a string, returns `io::Result<usize>`
-            let mut s = String::new();
-            f.read_to_string(&mut s)?;
+            // the catalog directory is simply the parent of the aggregated file
+            let dir = file_name.parent().unwrap_or_else(|| Path::new(""));
 
-            // check if we have yaml or json in the raw data
-            if s.contains('{') {
-                // break the declarative config into chunks
-                // similar to what ibm have done in the breakdown of catalogs
-                if file_name_str.contains("catalog.json") || file_name_str.contains("index.json") {
-                    let mut chunks = s.split("}\n{");
-                    let count = chunks.clone().count();
-                    if count <= 1 {
-                        chunks = s.split("}{")
+            // stream json straight off a BufReader so multi-hundred-MB catalogs
+            // are never pulled fully into memory (serde_yaml buffers the whole
+            // input itself), and let serde walk the top-level objects or
+            // documents natively instead of splitting the raw text on braces
+            let reader = BufReader::new(File::open(file_name)?);
+
+            if is_json {
+                for dc in serde_json::Deserializer::from_reader(reader).into_iter::<Self>() {
+                    match dc {
+                        Ok(dc) => Self::write_updated_config(dir, component, dc),
+                        Err(err) => warn!("could not parse : {:#?} : {}", &component, err),
                     }
-                    let l = chunks.clone().count();
-                    let mut update = "".to_string();
-                    for (pos, item) in chunks.enumerate() {
-                        // needs some refactoring
-                        // first chunk
-                        if pos == 0 {
-                            update = item.to_string() + "}";
-                        }
-                        // last chunk
-                        if pos == l - 1 {
-                            update = "{".to_string() + item;
-                        }
-                        // everything in between
-                        if pos > 0 && pos <= l - 2 {
-                            update = "{".to_string() + item + "}";
-                        }
-
-                        // shadow update with a replace "null" - absolute crap usage of json,
-                        // not sure why anyone would throw in a null or random value
-                        let re = Regex::new(
-                                r"(\x22value\x22: [0-9\.]+)|(\x22value\x22: \x22[0-9\.]+\x22)|(\x22value\x22: null)",
-                            ).unwrap();
-                        let new_update = re.replace_all(&update, "\"value\": {\"group\":\"\"}");
-                        let mut dir = file_name_str.split("catalog.json").nth(0).unwrap();
-                        if dir.contains("index.json") {
-                            dir = file_name_str.split("index.json").nth(0).unwrap();
-                        }
-
-                        // parse the file (we know its json)
-                        let dc = serde_json::from_str::<Self>(&new_update);
-                        match dc {
-                            Ok(dc) => {
-                                let name = dc.name.clone();
-                                if name.is_some() {
-                                    // now marshal to json (this cleans all unwanted fields)
-                                    // and finally write to disk
-
-                                    let json_contents = serde_json::to_string(&dc).unwrap();
-                                    let update_dir = Path::new(dir).join("updated-configs");
-                                    fs::create_dir_all(&update_dir).expect("must create dir");
-                                    fs::write(
-                                        update_dir.join(name.unwrap() + ".json"),
-                                        json_contents.as_str(),
-                                    )
-                                    .expect("must write updated json file");
-                                } else {
-                                    warn!(
-                                        "could not decode decalarative config for {}",
-                                        &component
-                                    );
-                                }
-                            }
-                            Err(err) => {
-                                warn!("could not parse : {:#?} : {} : {}", &component, pos, err);
-                            }
-                        }
+                }
+            } else {
+                for doc in serde_yaml::Deserializer::from_reader(reader) {
+                    match <Self as serde::de::Deserialize>::deserialize(doc) {
+                        Ok(dc) => Self::write_updated_config(dir, component, dc),
+                        Err(err) => warn!("could not parse : {:#?} : {}", &component, err),
                     }
                 }
             }
@@ -192,6 +147,24 @@ impl DeclarativeConfig {
         Ok(())
     }
 
+    // marshal a single declarative config back to json (this cleans all
+    // unwanted fields) and write it to the `updated-configs` directory
+    fn write_updated_config(dir: &Path, component: &str, dc: Self) {
+        let name = dc.name.clone();
+        if name.is_some() {
+            let json_contents = serde_json::to_string(&dc).unwrap();
+            let update_dir = dir.join("updated-configs");
+            fs::create_dir_all(&update_dir).expect("must create dir");
+            fs::write(
+                update_dir.join(name.unwrap() + ".json"),
+                json_contents.as_str(),
+            )
+            .expect("must write updated json file");
+        } else {
+            warn!("could not decode decalarative config for {}", component);
+        }
+    }
+
     pub fn get_declarativeconfig_map<P>(base_dir: P) -> HashMap<String, Self>
     where
         P: AsRef<Path>,